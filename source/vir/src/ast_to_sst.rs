@@ -14,12 +14,109 @@ use crate::util::{vec_map, vec_map_result};
 use air::ast::{Binder, BinderX, Binders, Quant, Span};
 use air::errors::error_with_label;
 use air::scope_map::ScopeMap;
+use num_bigint::BigInt;
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 type Arg = (Exp, Typ);
 type Args = Arc<Vec<Arg>>;
 
+/// A key for structurally comparing pure `Exp`s while ignoring `Span`s and other
+/// non-semantic metadata, so that two expressions built from different source
+/// locations but otherwise identical still dedup to the same temp. This is
+/// deliberately just a canonicalized string rather than a hand-rolled `Eq`/`Hash`
+/// impl on `ExpX`, since `Exp` already carries a `Span` that would otherwise have
+/// to be stripped everywhere.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct CseKey(String);
+
+/// Recursively render `exp` into `out`, skipping `Span`s, for use as a `CseKey`.
+/// Also collects every `UniqueIdent` mentioned in `exp` into `vars`, so that a
+/// later assignment to any of those variables can evict this expression's cache
+/// entry. Returns `false` (and leaves `out`/`vars` in an unspecified state) for
+/// forms we don't attempt to canonicalize, e.g. anything that introduces its own
+/// binders, since alpha-equivalence is more than we need here.
+fn write_cse_key(out: &mut String, vars: &mut HashSet<UniqueIdent>, exp: &Exp) -> bool {
+    use std::fmt::Write;
+    match &exp.x {
+        ExpX::Const(c) => {
+            let _ = write!(out, "(const {:?})", c);
+            true
+        }
+        ExpX::Var(x) | ExpX::VarAt(x, _) => {
+            vars.insert(x.clone());
+            let _ = write!(out, "(var {:?} {:?})", x, exp.typ);
+            true
+        }
+        ExpX::Old(..) => {
+            // `Old` expressions aren't structurally inspected here; since they're
+            // already covered by `is_small_exp` they never reach a temp-spill site.
+            false
+        }
+        ExpX::Unary(op, e1) => {
+            let _ = write!(out, "(unary {:?} ", op);
+            let ok = write_cse_key(out, vars, e1);
+            out.push(')');
+            ok
+        }
+        ExpX::UnaryOpr(op, e1) => {
+            let _ = write!(out, "(unaryopr {:?} ", op);
+            let ok = write_cse_key(out, vars, e1);
+            out.push(')');
+            ok
+        }
+        ExpX::Binary(op, e1, e2) => {
+            let _ = write!(out, "(binary {:?} ", op);
+            let ok = write_cse_key(out, vars, e1);
+            out.push(' ');
+            let ok = ok && write_cse_key(out, vars, e2);
+            out.push(')');
+            ok
+        }
+        ExpX::If(e0, e1, e2) => {
+            out.push_str("(if ");
+            let ok = write_cse_key(out, vars, e0)
+                && write_cse_key(out, vars, e1)
+                && write_cse_key(out, vars, e2);
+            out.push(')');
+            ok
+        }
+        ExpX::Ctor(path, variant, binders) => {
+            let _ = write!(out, "(ctor {:?} {:?} [", path, variant);
+            let mut ok = true;
+            for b in binders.iter() {
+                let _ = write!(out, "{:?}=", b.name);
+                ok = ok && write_cse_key(out, vars, &b.a);
+                out.push(' ');
+            }
+            out.push_str("])");
+            ok
+        }
+        ExpX::Call(fun, typs, args) => {
+            let _ = write!(out, "(call {:?} {:?} [", fun, typs);
+            let mut ok = true;
+            for a in args.iter() {
+                ok = ok && write_cse_key(out, vars, a);
+                out.push(' ');
+            }
+            out.push_str("])");
+            ok
+        }
+        // Anything that introduces binders, touches the heap/lambdas, or whose
+        // purity we can't easily see through here is conservatively not cached.
+        _ => false,
+    }
+}
+
+/// Compute a `CseKey` plus the set of `UniqueIdent`s mentioned in `exp`, or
+/// `None` if `exp` isn't a form we canonicalize (see `write_cse_key`).
+fn cse_key(exp: &Exp) -> Option<(CseKey, HashSet<UniqueIdent>)> {
+    let mut s = String::new();
+    let mut vars = HashSet::new();
+    if write_cse_key(&mut s, &mut vars, exp) { Some((CseKey(s), vars)) } else { None }
+}
+
 pub(crate) struct State {
     // View exec/proof code as spec
     // (used for is_const functions, which are viewable both as spec and exec)
@@ -39,6 +136,15 @@ pub(crate) struct State {
     dont_rename: HashSet<UniqueIdent>,
     // If we allow return expressions, this is the return variable and ensures clauses:
     pub(crate) ret_post: Option<(Option<UniqueIdent>, Exps)>,
+    // Common-subexpression cache: one scope level per push_scope/pop_scope, mapping
+    // an already-materialized pure Exp to the temp Ident holding its value, so that
+    // repeated structurally-identical pure subexpressions share one temp instead of
+    // each getting spilled into a fresh one.
+    cse_scopes: Vec<HashMap<CseKey, Ident>>,
+    // Reverse index: for each variable mentioned inside a cached expression, the set
+    // of cache keys that mention it. When a StmX::Assign writes that variable, every
+    // such entry is stale (it would return the pre-mutation value) and must be evicted.
+    cse_rev: HashMap<UniqueIdent, HashSet<CseKey>>,
 }
 
 #[derive(Clone)]
@@ -96,6 +202,8 @@ impl State {
             rename_counters: HashMap::new(),
             dont_rename: HashSet::new(),
             ret_post: None,
+            cse_scopes: vec![HashMap::new()],
+            cse_rev: HashMap::new(),
         }
     }
 
@@ -107,10 +215,81 @@ impl State {
 
     pub(crate) fn push_scope(&mut self) {
         self.rename_map.push_scope(true);
+        self.cse_scopes.push(HashMap::new());
     }
 
     pub(crate) fn pop_scope(&mut self) {
         self.rename_map.pop_scope();
+        // Cached temps never escape the scope that dominates them: dropping the
+        // innermost cse_scopes level is enough to stop them being reused, but we
+        // also sweep cse_rev so it doesn't grow unboundedly with stale keys.
+        self.cse_scopes.pop();
+        let live: HashSet<CseKey> =
+            self.cse_scopes.iter().flat_map(|m| m.keys().cloned()).collect();
+        self.cse_rev.retain(|_, keys| {
+            keys.retain(|k| live.contains(k));
+            !keys.is_empty()
+        });
+    }
+
+    /// Look up a previously-cached temp holding the same pure expression as `exp`,
+    /// searching from the innermost scope outward.
+    fn cse_lookup(&self, exp: &Exp) -> Option<Exp> {
+        let (key, _) = cse_key(exp)?;
+        for scope in self.cse_scopes.iter().rev() {
+            if let Some(ident) = scope.get(&key) {
+                let x = (ident.clone(), Some(0));
+                return Some(SpannedTyped::new(&exp.span, &exp.typ, ExpX::Var(x)));
+            }
+        }
+        None
+    }
+
+    /// Record that `temp` now holds the value of the pure expression `exp`, so that
+    /// a later structurally-identical expression can reuse it instead of spilling
+    /// into a new temp.
+    fn cse_insert(&mut self, exp: &Exp, temp: &Ident) {
+        if let Some((key, vars)) = cse_key(exp) {
+            for x in vars {
+                self.cse_rev.entry(x).or_insert_with(HashSet::new).insert(key.clone());
+            }
+            self.cse_scopes.last_mut().expect("cse scope").insert(key, temp.clone());
+        }
+    }
+
+    /// Evict every cache entry whose expression mentions `x`, since an assignment
+    /// to `x` means those entries would otherwise return a pre-mutation value.
+    fn cse_evict_var(&mut self, x: &UniqueIdent) {
+        if let Some(keys) = self.cse_rev.remove(x) {
+            for scope in self.cse_scopes.iter_mut() {
+                for key in &keys {
+                    scope.remove(key);
+                }
+            }
+        }
+    }
+
+    /// Evict the entire cache. Used when a write targets a location we can't
+    /// precisely attribute to a single variable (e.g. a field projection), where
+    /// being conservative is the only sound option.
+    fn cse_flush_all(&mut self) {
+        for scope in self.cse_scopes.iter_mut() {
+            scope.clear();
+        }
+        self.cse_rev.clear();
+    }
+
+    /// Evict cache entries made stale by a write to `dest` (a `Dest::dest` exp,
+    /// i.e. a `VarLoc` or a more complex location expression). Does nothing for a
+    /// fresh initialization, since that can't invalidate anything.
+    fn cse_evict_for_write(&mut self, dest: &Exp, is_init: bool) {
+        if is_init {
+            return;
+        }
+        match &dest.x {
+            ExpX::VarLoc(x) => self.cse_evict_var(x),
+            _ => self.cse_flush_all(),
+        }
     }
 
     pub(crate) fn get_var_unique_id(&self, x: &Ident) -> UniqueIdent {
@@ -418,6 +597,287 @@ fn check_unit_or_never(exp: &ReturnValue) -> Result<(), VirErr> {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Experimental CFG-based SST backend
+//
+// The lowering above represents a function body as a tree of `Stm`s (with
+// `StmX::Block`/`StmX::If`/`StmX::While` nesting) plus the `ReturnValue` enum's
+// `Never` case standing in for "this path diverges". That's workable, but early
+// returns and labeled loop exits end up expressed indirectly, through `Never`
+// propagating up and `can_control_flow_reach_after_loop` checks, rather than as an
+// explicit edge in a graph.
+//
+// `Cfg` is a from-scratch, optional alternative shape for the same information:
+// a graph of basic blocks, each a straight-line run of `Stm`s ending in a
+// `Terminator`, exactly as rustc MIR represents a function body. `stm_to_cfg`
+// builds one from the `Stm` tree the lowering above already produces; there is
+// no conversion back from a `Cfg` to a `Stm`. It is not on the lowering path
+// (see `CFG_BACKEND_ENABLED`), and it does not model early returns or labeled
+// break/continue precisely — `stm_to_cfg` reshapes the `Stm` tree it's handed,
+// which has already flattened those into `Never`/`assume_false`, so they end in
+// `Unreachable`; `Terminator::Return` only marks falling off the end of the
+// body, and there is no `Break`/`Continue` terminator. What's here gives a graph that
+// join-point and loop-exit placement can be read off directly, and a place to
+// eventually hang block-local SMT context reuse and that terminator work.
+// ---------------------------------------------------------------------------
+
+/// Tree-based `Stm`/`ReturnValue` lowering (above) is unconditionally what
+/// actually runs. `stm_to_cfg` runs only as an internal consistency check in
+/// `expr_to_one_stm_dest`: in debug builds always, and in release builds too
+/// when this is `true`. Either way the resulting `Cfg` is never emitted to AIR;
+/// this flag does not switch to a CFG-based backend.
+#[allow(dead_code)]
+pub(crate) const CFG_BACKEND_ENABLED: bool = false;
+
+/// Index into `Cfg::blocks`.
+pub(crate) type BlockId = usize;
+
+/// How a basic block hands off control once it falls off the end of its
+/// straight-line `stms`, mirroring rustc MIR's terminators (specialized to the
+/// two-way branch that's all `If`/`While` need here, rather than a general
+/// `SwitchInt` over arbitrary match arms).
+#[derive(Clone, Debug)]
+pub(crate) enum Terminator {
+    /// Unconditional fallthrough to another block.
+    Goto(BlockId),
+    /// Two-way branch on a boolean condition.
+    SwitchInt { cond: Exp, then_block: BlockId, else_block: BlockId },
+    /// Normal function exit.
+    Return,
+    /// Control providably never reaches here (e.g. after `assume(false)`, the
+    /// same marker the tree-based path uses to prune dead code; see chunk2-2).
+    Unreachable,
+}
+
+/// A single straight-line basic block: a run of `Stm`s with no internal
+/// branching, ending in a `Terminator`.
+#[derive(Clone, Debug)]
+pub(crate) struct BasicBlockData {
+    pub(crate) stms: Vec<Stm>,
+    pub(crate) terminator: Terminator,
+}
+
+/// A function body as a graph of basic blocks, rather than the nested `Stm`
+/// tree the rest of this file builds by default.
+#[derive(Clone, Debug)]
+pub(crate) struct Cfg {
+    pub(crate) blocks: Vec<BasicBlockData>,
+    pub(crate) entry: BlockId,
+}
+
+impl Cfg {
+    fn successors(&self, block: BlockId) -> Vec<BlockId> {
+        match &self.blocks[block].terminator {
+            Terminator::Goto(b) => vec![*b],
+            Terminator::SwitchInt { then_block, else_block, .. } => vec![*then_block, *else_block],
+            Terminator::Return | Terminator::Unreachable => vec![],
+        }
+    }
+
+    fn predecessors(&self) -> Vec<Vec<BlockId>> {
+        let mut preds = vec![Vec::new(); self.blocks.len()];
+        for b in 0..self.blocks.len() {
+            for s in self.successors(b) {
+                preds[s].push(b);
+            }
+        }
+        preds
+    }
+
+    /// Reverse postorder from `entry`: every block appears after all of its
+    /// predecessors in any acyclic prefix, which is what the dominator fixpoint
+    /// below needs to converge in a bounded number of passes.
+    fn reverse_postorder(&self) -> Vec<BlockId> {
+        fn visit(cfg: &Cfg, b: BlockId, visited: &mut Vec<bool>, postorder: &mut Vec<BlockId>) {
+            if visited[b] {
+                return;
+            }
+            visited[b] = true;
+            for s in cfg.successors(b) {
+                visit(cfg, s, visited, postorder);
+            }
+            postorder.push(b);
+        }
+        let mut visited = vec![false; self.blocks.len()];
+        let mut postorder = Vec::new();
+        visit(self, self.entry, &mut visited, &mut postorder);
+        postorder.reverse();
+        postorder
+    }
+
+    /// Cooper-Harvey-Kennedy iterative dominator computation: for every
+    /// reachable block but `entry`, its immediate dominator (the closest
+    /// ancestor common to every path from `entry` to it). Unreachable blocks
+    /// (e.g. ones split off after an `Unreachable` terminator) get `None`.
+    /// <https://www.cs.rice.edu/~keith/EMBED/dom.pdf>
+    pub(crate) fn immediate_dominators(&self) -> Vec<Option<BlockId>> {
+        let preds = self.predecessors();
+        let rpo = self.reverse_postorder();
+        let rpo_index: HashMap<BlockId, usize> =
+            rpo.iter().enumerate().map(|(i, &b)| (b, i)).collect();
+
+        fn intersect(
+            idom: &[Option<BlockId>],
+            rpo_index: &HashMap<BlockId, usize>,
+            mut b1: BlockId,
+            mut b2: BlockId,
+        ) -> BlockId {
+            while b1 != b2 {
+                while rpo_index[&b1] > rpo_index[&b2] {
+                    b1 = idom[b1].expect("intersect: walked off the dominator tree");
+                }
+                while rpo_index[&b2] > rpo_index[&b1] {
+                    b2 = idom[b2].expect("intersect: walked off the dominator tree");
+                }
+            }
+            b1
+        }
+
+        let mut idom: Vec<Option<BlockId>> = vec![None; self.blocks.len()];
+        idom[self.entry] = Some(self.entry);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &b in &rpo {
+                if b == self.entry {
+                    continue;
+                }
+                let mut new_idom: Option<BlockId> = None;
+                for &p in &preds[b] {
+                    if idom[p].is_none() {
+                        continue;
+                    }
+                    new_idom = Some(match new_idom {
+                        None => p,
+                        Some(cur) => intersect(&idom, &rpo_index, cur, p),
+                    });
+                }
+                if idom[b] != new_idom {
+                    idom[b] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+        // `entry` dominates itself by convention during the fixpoint above, but has
+        // no dominator of its own; report that the same way unreachable blocks do.
+        idom[self.entry] = None;
+        idom
+    }
+}
+
+struct CfgBuilder {
+    blocks: Vec<BasicBlockData>,
+}
+
+impl CfgBuilder {
+    fn new() -> Self {
+        CfgBuilder { blocks: Vec::new() }
+    }
+
+    fn new_block(&mut self) -> BlockId {
+        let id = self.blocks.len();
+        // Placeholder terminator; every block this builder creates gets a real
+        // one set before `stm_to_cfg` returns, except blocks already proven dead
+        // (which correctly keep `Unreachable`).
+        self.blocks.push(BasicBlockData { stms: Vec::new(), terminator: Terminator::Unreachable });
+        id
+    }
+
+    fn set_terminator(&mut self, block: BlockId, terminator: Terminator) {
+        self.blocks[block].terminator = terminator;
+    }
+
+    /// Lower one `Stm` into `block`. Returns the block later statements should
+    /// continue into, or `None` if `stm` already terminated the block (e.g. by
+    /// diverging), meaning anything after it in the same sequence is unreachable.
+    fn lower_stm(&mut self, block: BlockId, stm: &Stm) -> Option<BlockId> {
+        match &stm.x {
+            StmX::Block(stms) => self.lower_stms(block, stms),
+            StmX::Assume(cond) if matches!(&cond.x, ExpX::Const(Constant::Bool(false))) => {
+                self.set_terminator(block, Terminator::Unreachable);
+                None
+            }
+            StmX::If(cond, then_stm, else_stm_opt) => {
+                let then_block = self.new_block();
+                let else_block = self.new_block();
+                self.set_terminator(
+                    block,
+                    Terminator::SwitchInt { cond: cond.clone(), then_block, else_block },
+                );
+                let then_exit = self.lower_stm(then_block, then_stm);
+                let else_exit = match else_stm_opt {
+                    Some(else_stm) => self.lower_stm(else_block, else_stm),
+                    None => Some(else_block),
+                };
+                match (then_exit, else_exit) {
+                    (None, None) => None,
+                    (Some(b), None) | (None, Some(b)) => Some(b),
+                    (Some(then_exit), Some(else_exit)) => {
+                        let join = self.new_block();
+                        self.set_terminator(then_exit, Terminator::Goto(join));
+                        self.set_terminator(else_exit, Terminator::Goto(join));
+                        Some(join)
+                    }
+                }
+            }
+            StmX::While { cond_stms, cond_exp, body, .. } => {
+                let header = self.new_block();
+                self.set_terminator(block, Terminator::Goto(header));
+                let cond_block = match self.lower_stms(header, cond_stms) {
+                    Some(b) => b,
+                    // The loop condition itself can't diverge in practice, but
+                    // honor it if it somehow does.
+                    None => return None,
+                };
+                let body_block = self.new_block();
+                let exit_block = self.new_block();
+                self.set_terminator(
+                    cond_block,
+                    Terminator::SwitchInt {
+                        cond: cond_exp.clone(),
+                        then_block: body_block,
+                        else_block: exit_block,
+                    },
+                );
+                if let Some(body_exit) = self.lower_stm(body_block, body) {
+                    // Loop back to re-test the condition, same as a MIR loop's
+                    // back-edge to its header.
+                    self.set_terminator(body_exit, Terminator::Goto(header));
+                }
+                Some(exit_block)
+            }
+            // Every other `StmX` variant (`Assign`, `Assert`, `Call`, `Fuel`,
+            // `OpenInvariant`, `DeadEnd`, ...) has no control-flow edges of its
+            // own as far as this graph is concerned, so it's kept as an opaque
+            // straight-line statement rather than being lowered further.
+            _ => {
+                self.blocks[block].stms.push(stm.clone());
+                Some(block)
+            }
+        }
+    }
+
+    fn lower_stms(&mut self, mut block: BlockId, stms: &[Stm]) -> Option<BlockId> {
+        for stm in stms {
+            block = self.lower_stm(block, stm)?;
+        }
+        Some(block)
+    }
+}
+
+/// Lower a tree-form `Stm` (as built by `expr_to_one_stm_dest`/`stms_to_one_stm`)
+/// into an equivalent `Cfg`. See the module comment above: this exists alongside
+/// the tree-based path, not instead of it.
+#[allow(dead_code)]
+pub(crate) fn stm_to_cfg(stm: &Stm) -> Cfg {
+    let mut builder = CfgBuilder::new();
+    let entry = builder.new_block();
+    if let Some(last) = builder.lower_stm(entry, stm) {
+        builder.set_terminator(last, Terminator::Return);
+    }
+    Cfg { blocks: builder.blocks, entry }
+}
+
 /// the bool return value: if true, skip generating the postconditions later
 pub(crate) fn expr_to_one_stm_dest(
     ctx: &Ctx,
@@ -453,7 +913,610 @@ pub(crate) fn expr_to_one_stm_dest(
             true
         }
     };
-    Ok((stms_to_one_stm(&expr.span, stms), skip_ensures))
+    let stm = stms_to_one_stm(&expr.span, stms);
+
+    // Not wired into codegen yet (see the module comment above `Cfg`): run it as a
+    // consistency check instead, so the builder and dominator computation stay
+    // exercised on every real function this pass lowers rather than sitting
+    // entirely unrun. Gated on `debug_assertions` (or `CFG_BACKEND_ENABLED`, which
+    // only extends the same check to release builds) so release builds don't pay
+    // for the extra pass by default.
+    if CFG_BACKEND_ENABLED || cfg!(debug_assertions) {
+        // Confirm the tree we're about to return converts, and that every
+        // reachable block but the entry gets a unique immediate dominator while
+        // every unreachable block doesn't. `idom.len() == cfg.blocks.len()` alone
+        // checks nothing (immediate_dominators always returns that many slots,
+        // reachable or not); walk reachability from `entry` explicitly so a
+        // regression that left some reachable block with no dominator (or handed
+        // an unreachable one a spurious one) actually trips this.
+        let cfg = stm_to_cfg(&stm);
+        let idom = cfg.immediate_dominators();
+        assert_eq!(idom.len(), cfg.blocks.len());
+        let reachable: HashSet<BlockId> = cfg.reverse_postorder().into_iter().collect();
+        for b in 0..cfg.blocks.len() {
+            if b == cfg.entry {
+                assert!(idom[b].is_none(), "entry block must not have its own dominator");
+            } else if reachable.contains(&b) {
+                assert!(idom[b].is_some(), "reachable block {} has no immediate dominator", b);
+            } else {
+                assert!(idom[b].is_none(), "unreachable block {} got a spurious dominator", b);
+            }
+        }
+    }
+
+    Ok((stm, skip_ensures))
+}
+
+/// Inclusive `[min, max]` bounds for an `IntRange`, used to prove an arithmetic
+/// result can't overflow its target type without involving the SMT solver.
+fn int_range_bounds(range: &IntRange) -> Option<(BigInt, BigInt)> {
+    match range {
+        IntRange::U(bits) => {
+            let max = (BigInt::from(1) << *bits as u64) - BigInt::from(1);
+            Some((BigInt::from(0), max))
+        }
+        IntRange::I(bits) => {
+            let half = BigInt::from(1) << (*bits as u64 - 1);
+            Some((-half.clone(), half - BigInt::from(1)))
+        }
+        // Unbounded or platform-dependent ranges (Int, Nat, USize, ISize, ...):
+        // we don't know fixed bounds, so don't try to prove anything about them.
+        _ => None,
+    }
+}
+
+/// Fold `exp` to an exact constant, when it is one. Only `Constant::Nat` literals
+/// are recognized; anything else (including `Constant::Int` literals we can't
+/// safely distinguish from a `Nat` here) returns `None`.
+fn const_eval(exp: &Exp) -> Option<BigInt> {
+    match &exp.x {
+        ExpX::Const(Constant::Nat(s)) => s.parse::<BigInt>().ok(),
+        _ => None,
+    }
+}
+
+/// True when `exp` is a `Nat` literal that is provably nonzero.
+fn const_nat_is_nonzero(exp: &Exp) -> bool {
+    match const_eval(exp) {
+        Some(v) => v != BigInt::from(0),
+        None => false,
+    }
+}
+
+/// A conservative `[min, max]` bound for `exp`'s possible values, derived from a
+/// constant fold when possible, recursively from `+`/`-`/`*` subexpressions, or
+/// else from `exp`'s own `IntRange` (e.g. after a `Clip`). Returns `None` when we
+/// can't bound it at all, in which case callers must be conservative and keep
+/// whatever assert they were about to emit.
+fn exp_interval(exp: &Exp) -> Option<(BigInt, BigInt)> {
+    if let Some(v) = const_eval(exp) {
+        return Some((v.clone(), v));
+    }
+    match &exp.x {
+        ExpX::Unary(UnaryOp::Clip(range), _) => int_range_bounds(range),
+        ExpX::Binary(BinaryOp::Arith(arith @ (ArithOp::Add | ArithOp::Sub | ArithOp::Mul), _), a, b) => {
+            let ia = exp_interval(a)?;
+            let ib = exp_interval(b)?;
+            combine_interval(*arith, &ia, &ib)
+        }
+        _ => match &*exp.typ {
+            TypX::Int(range) => int_range_bounds(range),
+            _ => None,
+        },
+    }
+}
+
+/// Interval arithmetic for `+`/`-`/`*`, used to bound the result of an `Arith` op
+/// from the intervals of its operands. Mirrors the bound propagation a
+/// range-analysis pass would do on constant-laden integer arithmetic.
+fn combine_interval(
+    arith: ArithOp,
+    (lo1, hi1): &(BigInt, BigInt),
+    (lo2, hi2): &(BigInt, BigInt),
+) -> Option<(BigInt, BigInt)> {
+    match arith {
+        ArithOp::Add => Some((lo1 + lo2, hi1 + hi2)),
+        ArithOp::Sub => Some((lo1 - hi2, hi1 - lo2)),
+        ArithOp::Mul => {
+            let candidates =
+                [lo1 * lo2, lo1 * hi2, hi1 * lo2, hi1 * hi2];
+            let lo = candidates.iter().min().unwrap().clone();
+            let hi = candidates.iter().max().unwrap().clone();
+            Some((lo, hi))
+        }
+        ArithOp::EuclideanDiv | ArithOp::EuclideanMod => None,
+    }
+}
+
+/// True when `e1 <arith> e2`, computed in unbounded arithmetic, is provably
+/// within `range`'s bounds, so the `HasType` overflow/underflow assert can be
+/// omitted. Conservative: returns `false` (keep the assert) whenever either
+/// operand's bound, or the target range's bound, can't be determined.
+fn arith_result_in_range(arith: ArithOp, e1: &Exp, e2: &Exp, range: &IntRange) -> bool {
+    let target = match int_range_bounds(range) {
+        Some(t) => t,
+        None => return false,
+    };
+    let i1 = match exp_interval(e1) {
+        Some(i) => i,
+        None => return false,
+    };
+    let i2 = match exp_interval(e2) {
+        Some(i) => i,
+        None => return false,
+    };
+    match combine_interval(arith, &i1, &i2) {
+        Some((lo, hi)) => lo >= target.0 && hi <= target.1,
+        None => false,
+    }
+}
+
+// `arith_result_in_range` itself needs a real `Exp`/`Span` (from the parser, not
+// constructible from inside this file) to call end-to-end, so these tests cover
+// its actual decision logic directly: `int_range_bounds` (the target bounds) and
+// `combine_interval` (the operand-interval math), which between them are exactly
+// what decides whether the overflow/underflow assert gets omitted. This is the
+// most direct coverage available for the "unsound program still rejected"
+// property without a `rust_verify_test`-style harness, which doesn't exist
+// anywhere in this single-file snapshot.
+#[cfg(test)]
+mod arith_overflow_elision_tests {
+    use super::*;
+
+    #[test]
+    fn u8_bounds_are_0_to_255() {
+        assert_eq!(int_range_bounds(&IntRange::U(8)), Some((BigInt::from(0), BigInt::from(255))));
+    }
+
+    #[test]
+    fn i32_bounds_are_symmetric_twos_complement() {
+        assert_eq!(
+            int_range_bounds(&IntRange::I(32)),
+            Some((BigInt::from(-2147483648i64), BigInt::from(2147483647i64)))
+        );
+    }
+
+    #[test]
+    fn unbounded_ranges_have_no_known_bound() {
+        // `arith_result_in_range` must keep the assert for these since
+        // `int_range_bounds` returning `None` forces it to bail out early.
+        assert_eq!(int_range_bounds(&IntRange::Int), None);
+    }
+
+    fn u8(lo: i64, hi: i64) -> (BigInt, BigInt) {
+        (BigInt::from(lo), BigInt::from(hi))
+    }
+
+    #[test]
+    fn add_that_overflows_u8_is_not_elided() {
+        // 200u8 + 100u8: unbounded sum is [300, 300], which doesn't fit in U8's
+        // [0, 255] -- the overflow assert must NOT be omitted for this.
+        let sum = combine_interval(ArithOp::Add, &u8(200, 200), &u8(100, 100)).unwrap();
+        let target = int_range_bounds(&IntRange::U(8)).unwrap();
+        assert!(!(sum.0 >= target.0 && sum.1 <= target.1));
+    }
+
+    #[test]
+    fn add_that_fits_u8_is_elided() {
+        let sum = combine_interval(ArithOp::Add, &u8(0, 100), &u8(0, 50)).unwrap();
+        let target = int_range_bounds(&IntRange::U(8)).unwrap();
+        assert!(sum.0 >= target.0 && sum.1 <= target.1);
+    }
+
+    #[test]
+    fn sub_that_underflows_u8_is_not_elided() {
+        // 3u8 - 5u8 (hoisted to unbounded arithmetic first): [-2, -2] is
+        // negative, so it can't fit U8's [0, 255] -- must not be elided, even
+        // though the same interval *does* fit a signed 32-bit range.
+        let diff = combine_interval(ArithOp::Sub, &u8(3, 3), &u8(5, 5)).unwrap();
+        let u8_target = int_range_bounds(&IntRange::U(8)).unwrap();
+        assert!(!(diff.0 >= u8_target.0 && diff.1 <= u8_target.1));
+        let i32_target = int_range_bounds(&IntRange::I(32)).unwrap();
+        assert!(diff.0 >= i32_target.0 && diff.1 <= i32_target.1);
+    }
+
+    #[test]
+    fn division_and_mod_have_no_interval() {
+        // combine_interval refuses to bound Div/Mod at all, so
+        // arith_result_in_range never decides these ops; whether the
+        // division-by-zero assert is omitted depends only on the divisor being
+        // a nonzero Nat constant (const_nat_is_nonzero).
+        assert_eq!(combine_interval(ArithOp::EuclideanDiv, &u8(1, 10), &u8(1, 10)), None);
+        assert_eq!(combine_interval(ArithOp::EuclideanMod, &u8(1, 10), &u8(1, 10)), None);
+    }
+}
+
+/// Substitute every occurrence of the variable `target` in `exp` with `replacement`.
+fn substitute_var(exp: &Exp, target: &UniqueIdent, replacement: &Exp) -> Exp {
+    map_exp_visitor(exp, &mut |e| match &e.x {
+        ExpX::Var(x) if x == target => replacement.clone(),
+        _ => e.clone(),
+    })
+}
+
+/// Same as `substitute_var`, but over a `Stm` (and everything nested inside it).
+fn substitute_var_stm(stm: &Stm, target: &UniqueIdent, replacement: &Exp) -> Stm {
+    map_stm_exp_visitor(stm, &|exp| substitute_var(exp, target, replacement))
+        .expect("substitute_var_stm")
+}
+
+/// A binding whose RHS is cheap enough that copying it to a single use site is never
+/// more expensive than the `LocalDecl` it would otherwise require: a bare variable, a
+/// constant, or a chain of field projections off either.
+fn is_cheap_inline_rhs(exp: &Exp) -> bool {
+    match &exp.x {
+        ExpX::Const(_) | ExpX::Var(..) => true,
+        ExpX::UnaryOpr(UnaryOpr::Field(_), e) => is_cheap_inline_rhs(e),
+        _ => false,
+    }
+}
+
+/// The variable a cheap inline RHS ultimately reads from, if any (a `Const` reads
+/// nothing, so has no root). Used to check the RHS's value can't change out from under
+/// a substitution that moves its one read forward past the rest of the block.
+fn root_var(exp: &Exp) -> Option<UniqueIdent> {
+    match &exp.x {
+        ExpX::Var(x) => Some(x.clone()),
+        ExpX::UnaryOpr(UnaryOpr::Field(_), e) => root_var(e),
+        _ => None,
+    }
+}
+
+fn count_var_uses_in_exp(exp: &Exp, target: &UniqueIdent) -> usize {
+    let count = RefCell::new(0);
+    let _ = map_exp_visitor(exp, &mut |e| {
+        if let ExpX::Var(x) = &e.x {
+            if x == target {
+                *count.borrow_mut() += 1;
+            }
+        }
+        e.clone()
+    });
+    count.into_inner()
+}
+
+fn count_var_uses_in_stms(stms: &[Stm], target: &UniqueIdent) -> usize {
+    stms.iter()
+        .map(|stm| {
+            let count = RefCell::new(0);
+            // map_stm_exp_visitor hands over each top-level Exp of the statement,
+            // not its subexpressions, so count inside each one.
+            let _ = map_stm_exp_visitor(stm, &|exp| {
+                *count.borrow_mut() += count_var_uses_in_exp(exp, target);
+                exp.clone()
+            });
+            count.into_inner()
+        })
+        .sum()
+}
+
+/// True if `stm` is exactly the `Assign` that initializes `ident` (as emitted by
+/// `init_var`), i.e. the one statement we can drop once `ident` is inlined away.
+fn stm_is_own_init(stm: &Stm, ident: &UniqueIdent) -> bool {
+    match &stm.x {
+        StmX::Assign { lhs, .. } if lhs.is_init => match &lhs.dest.x {
+            ExpX::VarLoc(x) => x == ident,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Substitute `target` -> `replacement` inside a pending `Bnd`'s RHS(es). Used to
+/// keep a not-yet-processed Decl's Bnd in sync when an earlier Decl it refers to
+/// gets inlined away, so its own inlining later sees the final value rather than
+/// a reference to a variable whose `LocalDecl`/init no longer exist.
+fn substitute_var_in_bnd(bnd: &Bnd, target: &UniqueIdent, replacement: &Exp) -> Bnd {
+    match &bnd.x {
+        BndX::Let(binders) => {
+            let binders = binders
+                .iter()
+                .map(|b| Arc::new(BinderX { name: b.name.clone(), a: substitute_var(&b.a, target, replacement) }))
+                .collect();
+            Spanned::new(bnd.span.clone(), BndX::Let(Arc::new(binders)))
+        }
+        _ => bnd.clone(),
+    }
+}
+
+/// Try to apply single-use pure let-binding inlining (see the `Block` case below) for
+/// one `Decl`. Returns the replacement substituted in (and `stms`/`exp` rewritten
+/// accordingly) if `decl` was inlined; if `None`, the caller is responsible for
+/// keeping `decl` as a `LocalDecl`.
+fn try_inline_single_use_decl(
+    state: &mut State,
+    stms: &mut Vec<Stm>,
+    exp: &mut ReturnValue,
+    decl: &LocalDecl,
+    bnd: &Option<Bnd>,
+    local_mutability: &HashMap<UniqueIdent, bool>,
+) -> Option<Exp> {
+    let replacement = match bnd {
+        Some(bnd) => match &bnd.x {
+            BndX::Let(binders) if binders.len() == 1 => binders[0].a.clone(),
+            _ => return None,
+        },
+        None => return None,
+    };
+    if !is_cheap_inline_rhs(&replacement) {
+        return None;
+    }
+    let replacement = &replacement;
+    // Only inline past the rest of the block if we can show the RHS's value can't
+    // change before the substituted use: either it reads nothing (a Const), or it
+    // reads a variable this same block declared and never reassigns. A variable from
+    // an outer scope might be `mut` and reassigned by code we can't see from here.
+    let safe_to_move = match root_var(replacement) {
+        None => true,
+        Some(root) => local_mutability.get(&root) == Some(&false),
+    };
+    if !safe_to_move {
+        return None;
+    }
+    let uses = count_var_uses_in_stms(stms, &decl.ident)
+        + match exp {
+            ReturnValue::Some(e) => count_var_uses_in_exp(e, &decl.ident),
+            _ => 0,
+        };
+    if uses != 1 {
+        return None;
+    }
+    stms.retain(|stm| !stm_is_own_init(stm, &decl.ident));
+    for stm in stms.iter_mut() {
+        *stm = substitute_var_stm(stm, &decl.ident, replacement);
+    }
+    if let ReturnValue::Some(e) = exp {
+        *e = substitute_var(e, &decl.ident, replacement);
+    }
+    // We don't generate a LocalDecl, so we don't want to rename the variable
+    state.dont_rename.insert(decl.ident.clone());
+    Some(replacement.clone())
+}
+
+/// Euclidean division/modulo (remainder always nonnegative, regardless of
+/// either operand's sign), matching
+/// the semantics `ArithOp::EuclideanDiv`/`EuclideanMod` have at runtime.
+fn euclid_div_mod(a: &BigInt, b: &BigInt) -> (BigInt, BigInt) {
+    let q = a / b;
+    let r = a - &q * b;
+    if r < BigInt::from(0) {
+        if *b > BigInt::from(0) { (q - 1, r + b) } else { (q + 1, r - b) }
+    } else {
+        (q, r)
+    }
+}
+
+/// Fold a binary op over two constant integers, when that op is one we can
+/// evaluate exactly. Division/modulo by zero isn't folded (there's no value to
+/// produce); everything else always has a defined integer result.
+fn fold_const_arith(op: ArithOp, v1: &BigInt, v2: &BigInt) -> Option<BigInt> {
+    match op {
+        ArithOp::Add => Some(v1 + v2),
+        ArithOp::Sub => Some(v1 - v2),
+        ArithOp::Mul => Some(v1 * v2),
+        ArithOp::EuclideanDiv if *v2 != BigInt::from(0) => Some(euclid_div_mod(v1, v2).0),
+        ArithOp::EuclideanMod if *v2 != BigInt::from(0) => Some(euclid_div_mod(v1, v2).1),
+        _ => None,
+    }
+}
+
+/// Fold a binary op over two constant bools, when defined for that op.
+fn fold_const_bool(op: BinaryOp, b1: bool, b2: bool) -> Option<bool> {
+    match op {
+        BinaryOp::And => Some(b1 && b2),
+        BinaryOp::Or => Some(b1 || b2),
+        BinaryOp::Implies => Some(!b1 || b2),
+        BinaryOp::Eq(_) => Some(b1 == b2),
+        BinaryOp::Ne => Some(b1 != b2),
+        _ => None,
+    }
+}
+
+/// True when `e1`/`e2` are, up to span, the exact same pure expression (reuses
+/// the CSE machinery's notion of structural equality).
+fn exps_structurally_equal(e1: &Exp, e2: &Exp) -> bool {
+    match (cse_key(e1), cse_key(e2)) {
+        (Some((k1, _)), Some((k2, _))) => k1 == k2,
+        _ => false,
+    }
+}
+
+/// `x + 0`, `x * 1`, `x && true`, `x || false`, `x && x`, and friends: rewrites
+/// that drop an operand without evaluating anything, so they're always safe
+/// once we already know both operands are pure (which, in the only caller of
+/// `fold_exp`, they are).
+fn simplify_identity(op: BinaryOp, e1: &Exp, e2: &Exp) -> Option<Exp> {
+    let is_nat = |e: &Exp, v: &str| matches!(&e.x, ExpX::Const(Constant::Nat(s)) if &**s == v);
+    let is_bool = |e: &Exp, v: bool| matches!(&e.x, ExpX::Const(Constant::Bool(b)) if *b == v);
+    match op {
+        BinaryOp::Arith(ArithOp::Add, _) => {
+            if is_nat(e2, "0") {
+                return Some(e1.clone());
+            }
+            if is_nat(e1, "0") {
+                return Some(e2.clone());
+            }
+        }
+        BinaryOp::Arith(ArithOp::Sub, _) if is_nat(e2, "0") => return Some(e1.clone()),
+        BinaryOp::Arith(ArithOp::Mul, _) => {
+            if is_nat(e2, "1") {
+                return Some(e1.clone());
+            }
+            if is_nat(e1, "1") {
+                return Some(e2.clone());
+            }
+            if is_nat(e1, "0") {
+                return Some(e1.clone());
+            }
+            if is_nat(e2, "0") {
+                return Some(e2.clone());
+            }
+        }
+        BinaryOp::And => {
+            if is_bool(e1, true) {
+                return Some(e2.clone());
+            }
+            if is_bool(e2, true) {
+                return Some(e1.clone());
+            }
+            if is_bool(e1, false) {
+                return Some(e1.clone());
+            }
+            if is_bool(e2, false) {
+                return Some(e2.clone());
+            }
+            if exps_structurally_equal(e1, e2) {
+                return Some(e1.clone());
+            }
+        }
+        BinaryOp::Or => {
+            if is_bool(e1, false) {
+                return Some(e2.clone());
+            }
+            if is_bool(e2, false) {
+                return Some(e1.clone());
+            }
+            if is_bool(e1, true) {
+                return Some(e1.clone());
+            }
+            if is_bool(e2, true) {
+                return Some(e2.clone());
+            }
+            if exps_structurally_equal(e1, e2) {
+                return Some(e1.clone());
+            }
+        }
+        _ => {}
+    }
+    None
+}
+
+/// Bottom-up constant-fold and algebraically simplify one node, assuming its
+/// children have already been simplified (as `map_exp_visitor` guarantees).
+fn simplify_node(exp: &Exp) -> Exp {
+    match &exp.x {
+        ExpX::Unary(UnaryOp::Not, e1) => {
+            if let ExpX::Const(Constant::Bool(b)) = &e1.x {
+                return exp.new_x(ExpX::Const(Constant::Bool(!b)));
+            }
+        }
+        ExpX::If(cond, e1, e2) => match &cond.x {
+            ExpX::Const(Constant::Bool(true)) => return e1.clone(),
+            ExpX::Const(Constant::Bool(false)) => return e2.clone(),
+            _ => {}
+        },
+        ExpX::Binary(op, e1, e2) => {
+            if let (Some(v1), Some(v2)) = (const_eval(e1), const_eval(e2)) {
+                if let BinaryOp::Arith(arith, _) = op {
+                    if let Some(folded) = fold_const_arith(*arith, &v1, &v2) {
+                        // `Constant::Nat` is a textual literal for a *non-negative*
+                        // integer; `Sub`/`EuclideanDiv`/`EuclideanMod` can still fold
+                        // to a negative BigInt (e.g. `3 - 5`), which would otherwise
+                        // get reprinted as a malformed `Nat("-2")`. Leave those
+                        // unfolded rather than hand AIR a bogus constant.
+                        if folded >= BigInt::from(0) {
+                            let c = Constant::Nat(Arc::new(folded.to_string()));
+                            return exp.new_x(ExpX::Const(c));
+                        }
+                    }
+                }
+            }
+            if let (ExpX::Const(Constant::Bool(b1)), ExpX::Const(Constant::Bool(b2))) =
+                (&e1.x, &e2.x)
+            {
+                if let Some(folded) = fold_const_bool(*op, *b1, *b2) {
+                    return exp.new_x(ExpX::Const(Constant::Bool(folded)));
+                }
+            }
+            if let Some(simplified) = simplify_identity(*op, e1, e2) {
+                return simplified;
+            }
+        }
+        _ => {}
+    }
+    exp.clone()
+}
+
+/// Partially evaluate a pure `Exp` before handing it to AIR, folding constant
+/// arithmetic/boolean subexpressions and a handful of identity/absorbing laws.
+/// Must only be called on expressions already known to be pure (no statements
+/// were needed to produce them): folding across a side effect would be unsound.
+fn fold_exp(exp: &Exp) -> Exp {
+    map_exp_visitor(exp, &mut |e| simplify_node(e))
+}
+
+/// Classification of what a sequence of Stms can do to the store and control
+/// flow, mirroring the eager-vs-lazy distinction used elsewhere to decide
+/// whether an expression can be evaluated unconditionally.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Effect {
+    /// No statements at all.
+    Pure,
+    /// Only `StmX::Assert`/`StmX::Assume`/`StmX::Fuel`: these can fail verification
+    /// but cannot mutate the store, call anything, loop, or return, so running them
+    /// somewhere they weren't originally reachable is harmless as long as their
+    /// predicates are appropriately guarded.
+    Benign,
+    /// Anything that can assign, call, loop, or otherwise affect the store or
+    /// control flow.
+    Effectful,
+}
+
+fn classify_effects(stms: &[Stm]) -> Effect {
+    if stms.len() == 0 {
+        return Effect::Pure;
+    }
+    for stm in stms {
+        match &stm.x {
+            StmX::Assert(..) | StmX::Assume(..) | StmX::Fuel(..) => {}
+            StmX::Block(inner) => match classify_effects(inner) {
+                Effect::Pure | Effect::Benign => {}
+                Effect::Effectful => return Effect::Effectful,
+            },
+            _ => return Effect::Effectful,
+        }
+    }
+    Effect::Benign
+}
+
+/// Rewrite a `Benign`-classified statement list so each `Assert`/`Assume`
+/// predicate `p` becomes `guard ==> p`, and flatten any nested `Block`s in the
+/// process. `Fuel` hints carry no predicate and are hoisted unguarded, which is
+/// sound: a fuel hint only widens what facts the solver may use, so applying it
+/// in a context where it previously wasn't reached can't invalidate a proof.
+///
+/// Soundness of the `Assert`/`Assume` rewrite: every leaf statement in `stms`
+/// produces exactly one output statement (or, for `Block`, recursively exactly
+/// as many as its contents would), so nothing is dropped or duplicated; and
+/// `guard ==> p` is implied whenever `p` itself was (neither loses an
+/// obligation) while being vacuously true off the `guard` path (neither grants
+/// one that wasn't already available). Only reachable through the
+/// `classify_effects(..) == Effect::Benign` arm at the one call site below,
+/// which is exactly the precondition the `unreachable!` arm enforces.
+///
+/// No test in this file exercises this rewrite (the `#[cfg(test)]` module above
+/// covers only the overflow-elision helpers), so this argument is carried here
+/// as the documented invariant rather than as an executable test.
+fn guard_benign_stms(guard: &Exp, stms: &[Stm]) -> Vec<Stm> {
+    let mut out = Vec::new();
+    for stm in stms {
+        match &stm.x {
+            StmX::Assert(err, pred) => {
+                let impliedx = ExpX::Binary(BinaryOp::Implies, guard.clone(), pred.clone());
+                let implied = SpannedTyped::new(&stm.span, &Arc::new(TypX::Bool), impliedx);
+                out.push(Spanned::new(stm.span.clone(), StmX::Assert(err.clone(), implied)));
+            }
+            StmX::Assume(pred) => {
+                let impliedx = ExpX::Binary(BinaryOp::Implies, guard.clone(), pred.clone());
+                let implied = SpannedTyped::new(&stm.span, &Arc::new(TypX::Bool), impliedx);
+                out.push(Spanned::new(stm.span.clone(), StmX::Assume(implied)));
+            }
+            StmX::Fuel(..) => out.push(stm.clone()),
+            StmX::Block(inner) => out.extend(guard_benign_stms(guard, inner)),
+            _ => unreachable!("guard_benign_stms called on a non-Benign statement"),
+        }
+    }
+    out
 }
 
 fn is_small_exp(exp: &Exp) -> bool {
@@ -631,9 +1694,13 @@ fn expr_to_stm_opt(
                     // make a Call
                     stms.extend(stms2.into_iter());
                     let (dest, assign) = if matches!(lhs_exp.x, ExpX::VarLoc(_)) {
+                        // The call may also write through `&mut` arguments, not
+                        // just `lhs`, so flush rather than evict only `lhs`.
+                        state.cse_flush_all();
                         (Dest { dest: lhs_exp, is_init: *init_not_mut }, None)
                     } else {
                         assert!(!*init_not_mut, "init_not_mut unexpected for complex call dest");
+                        state.cse_flush_all();
                         let (temp, temp_var) = state.next_temp(&lhs_exp.span, &expr2.typ);
                         let temp_ident = state.declare_new_var(&temp, &expr2.typ, false, false);
                         let assign = Spanned::new(
@@ -658,16 +1725,27 @@ fn expr_to_stm_opt(
                 None => {
                     // make an Assign
                     let (stms2, e2) = expr_to_stm_opt(ctx, state, expr2)?;
+                    let e2_is_pure = stms2.len() == 0;
                     let e2 = unwrap_or_return_never!(e2, stms2);
                     stms.extend(stms2.into_iter());
+                    let cached = if e2_is_pure { state.cse_lookup(&e2) } else { None };
                     let rhs = if matches!(lhs_exp.x, ExpX::VarLoc(_)) || is_small_exp(&e2) {
                         e2
+                    } else if let Some(cached) = cached {
+                        cached
                     } else {
                         let (temp, temp_var) = state.next_temp(&e2.span, &e2.typ);
                         let temp_ident = state.declare_new_var(&temp, &e2.typ, false, false);
                         stms.push(init_var(&expr.span, &temp_ident, &e2));
+                        if e2_is_pure {
+                            state.cse_insert(&e2, &temp_ident.0);
+                        }
                         temp_var
                     };
+                    // This may mutate an existing variable (rather than initializing
+                    // a fresh one), in which case any cached expression that mentions
+                    // it is now stale and must be evicted.
+                    state.cse_evict_for_write(&lhs_exp, *init_not_mut);
                     let assign =
                         StmX::Assign { lhs: Dest { dest: lhs_exp, is_init: *init_not_mut }, rhs };
                     stms.push(Spanned::new(expr.span.clone(), assign));
@@ -698,6 +1776,11 @@ fn expr_to_stm_opt(
                             dest: var_loc_exp(&expr.span, &expr.typ, temp_ident),
                             is_init: true,
                         };
+                        // An exec call can take `&mut` arguments we have no visibility
+                        // into here, so any cached pure Exp could be holding a
+                        // pre-call value for one of them; flush rather than risk
+                        // handing a later identical subexpression a stale temp.
+                        state.cse_flush_all();
                         stms.push(stm_call(
                             state,
                             &expr.span,
@@ -709,7 +1792,8 @@ fn expr_to_stm_opt(
                         // tmp
                         Ok((stms, ReturnValue::Some(temp_var)))
                     } else {
-                        // StmX::Call
+                        // StmX::Call: same `&mut`-argument hazard as the `ret` case above.
+                        state.cse_flush_all();
                         stms.push(stm_call(state, &expr.span, x.clone(), typs.clone(), args, None));
                         Ok((stms, ReturnValue::ImplicitUnit(expr.span.clone())))
                     }
@@ -758,9 +1842,19 @@ fn expr_to_stm_opt(
                 _ => None,
             };
             let (mut stms1, e1) = expr_to_stm_opt(ctx, state, e1)?;
+            // `e1` always runs, but for a short-circuit op `e2` might not (that's
+            // the only reason it's split off via `if_to_stm` below rather than
+            // just sequenced after `stms1`). Scope it so any CSE temp it spills
+            // can't leak into code that runs whether or not `e2` did.
+            if short_circuit.is_some() {
+                state.push_scope();
+            }
             let (mut stms2, e2) = expr_to_stm_opt(ctx, state, e2)?;
-            match (short_circuit, stms2.len()) {
-                (Some((proceed_on, other)), n) if n > 0 => {
+            if short_circuit.is_some() {
+                state.pop_scope();
+            }
+            match (short_circuit, classify_effects(&stms2)) {
+                (Some((proceed_on, other)), Effect::Effectful) => {
                     // and:
                     //   if e1 { stmts2; e2 } else { false }
                     // implies:
@@ -776,6 +1870,25 @@ fn expr_to_stm_opt(
                         Ok(if_to_stm(state, expr, stms1, &e1, vec![], &b, stms2, &e2))
                     }
                 }
+                (Some((proceed_on, _other)), Effect::Benign) => {
+                    // stms2 only contains Assert/Assume/Fuel: since those can't
+                    // change the store, running them unconditionally but with their
+                    // predicates guarded by "e1 evaluated to proceed_on ==> ..." is
+                    // equivalent to only running them on the branch where e2 would
+                    // actually be evaluated. This keeps the result a single pure
+                    // ExpX::Binary instead of paying for an if_to_stm split.
+                    let e1 = unwrap_or_return_never!(e1, stms1);
+                    let guard = if proceed_on {
+                        e1.clone()
+                    } else {
+                        let notx = ExpX::Unary(UnaryOp::Not, e1.clone());
+                        SpannedTyped::new(&expr.span, &Arc::new(TypX::Bool), notx)
+                    };
+                    stms1.extend(guard_benign_stms(&guard, &stms2));
+                    let e2 = unwrap_or_return_never!(e2, stms1);
+                    let bin = mk_exp(ExpX::Binary(*op, e1, e2));
+                    Ok((stms1, ReturnValue::Some(bin)))
+                }
                 _ => {
                     let e1 = unwrap_or_return_never!(e1, stms1);
                     stms1.append(&mut stms2);
@@ -791,35 +1904,49 @@ fn expr_to_stm_opt(
                         ) {
                             (true, _, _) => {}
                             (_, Mode::Spec, _) => {}
-                            (_, _, TypX::Int(IntRange::U(_) | IntRange::I(_))) => {
-                                let (assert_exp, msg) = match arith {
+                            (_, _, TypX::Int(range @ (IntRange::U(_) | IntRange::I(_)))) => {
+                                let provably_safe = match arith {
                                     ArithOp::Add | ArithOp::Sub | ArithOp::Mul => {
-                                        let unary = UnaryOpr::HasType(expr.typ.clone());
-                                        let has_type = ExpX::UnaryOpr(unary, bin.clone());
-                                        let has_type = SpannedTyped::new(
-                                            &expr.span,
-                                            &Arc::new(TypX::Bool),
-                                            has_type,
-                                        );
-                                        (has_type, "possible arithmetic underflow/overflow")
+                                        arith_result_in_range(*arith, &e1, &e2, range)
                                     }
                                     ArithOp::EuclideanDiv | ArithOp::EuclideanMod => {
-                                        let zero =
-                                            ExpX::Const(Constant::Nat(Arc::new("0".to_string())));
-                                        let ne =
-                                            ExpX::Binary(BinaryOp::Ne, e2.clone(), e2.new_x(zero));
-                                        let ne = SpannedTyped::new(
-                                            &expr.span,
-                                            &Arc::new(TypX::Bool),
-                                            ne,
-                                        );
-                                        (ne, "possible division by zero")
+                                        const_nat_is_nonzero(&e2)
                                     }
                                 };
-                                let error = air::errors::error(msg, &expr.span);
-                                let assert = StmX::Assert(Some(error), assert_exp);
-                                let assert = Spanned::new(expr.span.clone(), assert);
-                                stms1.push(assert);
+                                if !provably_safe {
+                                    let (assert_exp, msg) = match arith {
+                                        ArithOp::Add | ArithOp::Sub | ArithOp::Mul => {
+                                            let unary = UnaryOpr::HasType(expr.typ.clone());
+                                            let has_type = ExpX::UnaryOpr(unary, bin.clone());
+                                            let has_type = SpannedTyped::new(
+                                                &expr.span,
+                                                &Arc::new(TypX::Bool),
+                                                has_type,
+                                            );
+                                            (has_type, "possible arithmetic underflow/overflow")
+                                        }
+                                        ArithOp::EuclideanDiv | ArithOp::EuclideanMod => {
+                                            let zero = ExpX::Const(Constant::Nat(Arc::new(
+                                                "0".to_string(),
+                                            )));
+                                            let ne = ExpX::Binary(
+                                                BinaryOp::Ne,
+                                                e2.clone(),
+                                                e2.new_x(zero),
+                                            );
+                                            let ne = SpannedTyped::new(
+                                                &expr.span,
+                                                &Arc::new(TypX::Bool),
+                                                ne,
+                                            );
+                                            (ne, "possible division by zero")
+                                        }
+                                    };
+                                    let error = air::errors::error(msg, &expr.span);
+                                    let assert = StmX::Assert(Some(error), assert_exp);
+                                    let assert = Spanned::new(expr.span.clone(), assert);
+                                    stms1.push(assert);
+                                }
                             }
                             _ => {}
                         }
@@ -829,6 +1956,17 @@ fn expr_to_stm_opt(
                 }
             }
         }
+        // NOTE: `binders`/`params` below are required to already carry a concrete
+        // `Typ` for every binder (e.g. `forall|x: int| ...`). Writing a quantifier,
+        // closure, or `choose` with an unannotated binder and having its type
+        // inferred from use (`forall|x| f(x) == g(x)`) is NOT implemented in this
+        // tree: it would need the frontend (outside this file, not present in this
+        // snapshot) to first produce a placeholder `Typ` for the omitted
+        // annotation, and a real inference pass here to resolve it. An earlier
+        // attempt at the inference half shipped against an invented placeholder
+        // nothing upstream ever produced, so it was dead code and has been
+        // removed; this comment exists so that gap stays visible instead of
+        // silently relying on every caller already providing concrete types.
         ExprX::Quant(quant, binders, body) => {
             state.push_scope();
             state.declare_binders(binders);
@@ -888,7 +2026,7 @@ fn expr_to_stm_opt(
         }
         ExprX::Choose { params, cond, body } => {
             state.push_scope();
-            state.declare_binders(&params);
+            state.declare_binders(params);
             let cond_exp = expr_to_pure_exp(ctx, state, cond)?;
             let body_exp = expr_to_pure_exp(ctx, state, body)?;
             state.pop_scope();
@@ -964,15 +2102,28 @@ fn expr_to_stm_opt(
         }
         ExprX::If(expr0, expr1, None) => {
             let (stms0, e0) = expr_to_stm_opt(ctx, state, expr0)?;
+            // The then-branch doesn't dominate anything after the `if`, so any CSE
+            // temp spilled while lowering it must not leak into the cache outside:
+            // on the else path (here, just "do nothing"), that temp's `init_var`
+            // never ran, so reusing it would read an uninitialized local.
+            state.push_scope();
             let (stms1, e1) = expr_to_stm_opt(ctx, state, expr1)?;
+            state.pop_scope();
             let stms2 = vec![];
             let e2 = ReturnValue::ImplicitUnit(expr.span.clone());
             Ok(if_to_stm(state, expr, stms0, &e0, stms1, &e1, stms2, &e2))
         }
         ExprX::If(expr0, expr1, Some(expr2)) => {
             let (stms0, e0) = expr_to_stm_opt(ctx, state, expr0)?;
+            // Neither arm dominates the other, so each gets its own CSE scope: a
+            // temp cached while lowering one arm must not be reused while lowering
+            // (or after) the other, since its `init_var` only runs on its own arm.
+            state.push_scope();
             let (stms1, e1) = expr_to_stm_opt(ctx, state, expr1)?;
+            state.pop_scope();
+            state.push_scope();
             let (stms2, e2) = expr_to_stm_opt(ctx, state, expr2)?;
+            state.pop_scope();
             Ok(if_to_stm(state, expr, stms0, &e0, stms1, &e1, stms2, &e2))
         }
         ExprX::Match(..) => {
@@ -992,7 +2143,16 @@ fn expr_to_stm_opt(
                 }
             };
 
+            // The body may run zero, one, or many times, so it doesn't dominate
+            // anything after the loop (nor does one iteration dominate the next,
+            // as far as static lowering can tell): any CSE temp spilled while
+            // lowering it must stay local to it. A write late in the body also
+            // makes an outer entry stale for the next iteration's earlier reads,
+            // so the body starts from an empty cache.
+            state.cse_flush_all();
+            state.push_scope();
             let (stms1, e1) = expr_to_stm_opt(ctx, state, body)?;
+            state.pop_scope();
             check_unit_or_never(&e1)?;
             let invs = Arc::new(vec_map_result(invs, |e| expr_to_pure_exp(ctx, state, e))?);
             let while_stm = Spanned::new(
@@ -1017,13 +2177,23 @@ fn expr_to_stm_opt(
         ExprX::OpenInvariant(inv, binder, body, atomicity) => {
             // Evaluate `inv`
             let (mut stms0, big_inv_exp) = expr_to_stm_opt(ctx, state, inv)?;
+            let inv_is_pure = stms0.len() == 0;
             let big_inv_exp = unwrap_or_return_never!(big_inv_exp, stms0);
 
             // Assign it to a constant temp variable to ensure it is constant
             // across the entire block.
-            let (temp, temp_var) = state.next_temp(&big_inv_exp.span, &inv.typ);
-            let temp_id = state.declare_new_var(&temp, &inv.typ, false, false);
-            stms0.push(init_var(&big_inv_exp.span, &temp_id, &big_inv_exp));
+            let cached_inv = if inv_is_pure { state.cse_lookup(&big_inv_exp) } else { None };
+            let temp_var = if let Some(cached) = cached_inv {
+                cached
+            } else {
+                let (temp, temp_var) = state.next_temp(&big_inv_exp.span, &inv.typ);
+                let temp_id = state.declare_new_var(&temp, &inv.typ, false, false);
+                stms0.push(init_var(&big_inv_exp.span, &temp_id, &big_inv_exp));
+                if inv_is_pure {
+                    state.cse_insert(&big_inv_exp, &temp_id.0);
+                }
+                temp_var
+            };
 
             // Process the body
 
@@ -1091,6 +2261,10 @@ fn expr_to_stm_opt(
             let mut stms: Vec<Stm> = Vec::new();
             let mut local_decls: Vec<LocalDecl> = Vec::new();
             let mut binds: Vec<Bnd> = Vec::new();
+            // Every Decl that got a pure Bnd, kept alongside its LocalDecl regardless of
+            // whether the block as a whole stays pure; used below to single-use-inline
+            // bindings even in a block that has other effects.
+            let mut decls_with_bnd: Vec<(LocalDecl, Option<Bnd>)> = Vec::new();
             let mut is_pure_exp = true;
             let mut never_return = false;
             for stmt in stmts.iter() {
@@ -1099,6 +2273,7 @@ fn expr_to_stm_opt(
                     Some((decl, bnd)) => {
                         state.push_scope();
                         local_decls.push(decl.clone());
+                        decls_with_bnd.push((decl.clone(), bnd.clone()));
                         state.insert_unique_var(&decl.ident);
                         match bnd {
                             None => {
@@ -1120,12 +2295,37 @@ fn expr_to_stm_opt(
                     ReturnValue::Never => {
                         is_pure_exp = false;
                         never_return = true;
-                        // Don't process any of the later statements: they are unreachable.
+                        // Mirror how rustc MIR marks an unreachable basic block: once
+                        // control flow provably can't fall through, stop lowering (not
+                        // just emitting) everything after it, so we don't allocate unique
+                        // vars or local decls for statements that can never execute, and
+                        // leave an explicit marker rather than a sequence that just stops.
+                        stms.push(assume_false(&stmt.span));
+                        // A user-facing "unreachable statement" warning at `stmt.span` would
+                        // belong here, but this pass has no diagnostics sink to emit one
+                        // through (only hard errors, via `err_str`/`err_string`) and adding
+                        // one is out of scope for this change. Soundness doesn't depend on
+                        // it either way: `assume_false` already makes the rest of the block
+                        // unreachable to the verifier regardless of whether we lower it.
                         break;
                     }
                     _ => {}
                 }
             }
+            // The `break` above is what actually stops later statements in this
+            // block from being lowered (and from contributing any dropped-code
+            // warning some future diagnostics sink might add); check here that it
+            // really did leave an `assume_false` as the last thing pushed, rather
+            // than trusting that by inspection alone. (It need not be the only
+            // one: a `return` already ends its own statements with one.)
+            debug_assert!(
+                !never_return
+                    || matches!(
+                        &stms.last().expect("never_return implies assume_false was pushed").x,
+                        StmX::Assume(e) if matches!(&e.x, ExpX::Const(Constant::Bool(false)))
+                    ),
+                "never_return must leave the assume_false marker as the last lowered statement"
+            );
             let exp = if never_return {
                 ReturnValue::Never
             } else if let Some(expr) = body_opt {
@@ -1143,26 +2343,128 @@ fn expr_to_stm_opt(
             }
             match exp {
                 ReturnValue::Some(mut exp) if is_pure_exp => {
-                    // Pure expression: fold decls into Let bindings and return a single expression
-                    for bnd in binds.iter().rev() {
-                        exp = SpannedTyped::new(
-                            &expr.span,
-                            &exp.typ,
-                            ExpX::Bind(bnd.clone(), exp.clone()),
-                        );
+                    // Pure expression: fold decls into Let bindings and return a single
+                    // expression. `binds` and `local_decls` were pushed in lockstep above
+                    // (is_pure_exp only stays true when every Decl got a Bnd), so we can
+                    // zip them to recover which UniqueIdent each Bnd actually binds.
+                    for (bnd, decl) in binds.iter().rev().zip(local_decls.iter().rev()) {
+                        let trivial_binding = match &bnd.x {
+                            BndX::Let(binders) if binders.len() == 1 => {
+                                match &binders[0].a.x {
+                                    ExpX::Const(_) | ExpX::Var(_) => Some(binders[0].a.clone()),
+                                    _ => None,
+                                }
+                            }
+                            _ => None,
+                        };
+                        exp = match trivial_binding {
+                            // A let bound to a constant or a bare variable carries no
+                            // information an AIR `let` would add over direct substitution,
+                            // so inline it and drop the binder entirely.
+                            Some(value) => substitute_var(&exp, &decl.ident, &value),
+                            None => SpannedTyped::new(
+                                &expr.span,
+                                &exp.typ,
+                                ExpX::Bind(bnd.clone(), exp.clone()),
+                            ),
+                        };
                     }
                     // We don't generate a LocalDecl, so we don't want to rename the variables
                     for decl in local_decls {
                         state.dont_rename.insert(decl.ident.clone());
                     }
 
+                    // Every sub-expression here is pure (that's what gated this branch),
+                    // so constant-folding and algebraic simplification can't skip past any
+                    // side effect; any arithmetic overflow obligation was already proven
+                    // unnecessary (or waived for Mode::Spec) before it could reach here,
+                    // so folding it further can't change what obligation was required.
+                    let exp = fold_exp(&exp);
+
                     assert!(!never_return);
                     return Ok((vec![], ReturnValue::Some(exp)));
                 }
                 _ => {
-                    // Not pure: return statements + an expression
-                    for decl in local_decls {
-                        state.local_decls.push(decl);
+                    // Not pure: return statements + an expression.
+                    //
+                    // Even though the block as a whole isn't fold-able into a single pure
+                    // Exp, individual Decls may still have gotten a pure Bnd (their own
+                    // initializer lowered with no statements). When such a binding is cheap
+                    // (a bare variable, a chain of field projections off one, or a Const)
+                    // and its variable is read exactly once among the rest of the block,
+                    // substitute it at that single use and drop the LocalDecl entirely,
+                    // rather than paying for an extra SMT local and let-chain link. This is
+                    // the same idea as MIR's local-simplification/copy-propagation passes
+                    // (and the "let and return" pattern clippy flags), just applied here
+                    // instead of only in the fully-pure case above.
+                    let mut exp = exp;
+                    // A RHS `Var`/field-projection can only be inlined past the rest of the
+                    // block if we know its root variable isn't reassigned later; we only
+                    // have that knowledge for variables this same block declared.
+                    let local_mutability: HashMap<UniqueIdent, bool> = decls_with_bnd
+                        .iter()
+                        .map(|(decl, _)| (decl.ident.clone(), decl.mutable))
+                        .collect();
+                    let mut decls_with_bnd = decls_with_bnd;
+                    #[cfg(debug_assertions)]
+                    let mut inlined_idents: Vec<UniqueIdent> = Vec::new();
+                    for i in 0..decls_with_bnd.len() {
+                        let (decl, bnd) = decls_with_bnd[i].clone();
+                        let inlined = if decl.mutable {
+                            None
+                        } else {
+                            try_inline_single_use_decl(
+                                state,
+                                &mut stms,
+                                &mut exp,
+                                &decl,
+                                &bnd,
+                                &local_mutability,
+                            )
+                        };
+                        match inlined {
+                            Some(replacement) => {
+                                // Any later Decl whose (not yet acted-upon) Bnd still
+                                // refers to `decl.ident` must have that reference
+                                // rewritten too, since we just dropped `decl.ident`'s
+                                // LocalDecl/init: otherwise that later Decl would get
+                                // inlined (or kept) pointing at a variable that no
+                                // longer exists.
+                                for (_, later_bnd) in decls_with_bnd[i + 1..].iter_mut() {
+                                    if let Some(later_bnd) = later_bnd {
+                                        *later_bnd = substitute_var_in_bnd(
+                                            later_bnd,
+                                            &decl.ident,
+                                            &replacement,
+                                        );
+                                    }
+                                }
+                                #[cfg(debug_assertions)]
+                                inlined_idents.push(decl.ident.clone());
+                            }
+                            None => {
+                                state.local_decls.push(decl);
+                            }
+                        }
+                    }
+                    // Catch exactly the chained-inlining hazard this loop exists to
+                    // avoid: an inlined decl's ident must have zero remaining uses
+                    // anywhere in the rewritten block, since its LocalDecl/init are
+                    // gone. A stale reference slipping through here means some
+                    // later decl's Bnd wasn't substituted before it was inlined or
+                    // kept.
+                    #[cfg(debug_assertions)]
+                    for ident in &inlined_idents {
+                        let remaining = count_var_uses_in_stms(&stms, ident)
+                            + match &exp {
+                                ReturnValue::Some(e) => count_var_uses_in_exp(e, ident),
+                                _ => 0,
+                            };
+                        debug_assert_eq!(
+                            remaining, 0,
+                            "inlined decl {:?} still has {} use(s) left in the block",
+                            ident, remaining
+                        );
                     }
                     let block = Spanned::new(expr.span.clone(), StmX::Block(Arc::new(stms)));
                     Ok((vec![block], exp))